@@ -2,17 +2,101 @@ use base64::{Engine as _, engine::general_purpose};
 use clap::Parser;
 use rusqlite::Connection;
 use serde_json::{Map, Value};
+use std::fmt;
 use std::fs::{self};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
+use std::sync::Arc;
+#[cfg(unix)]
+use std::thread;
+#[cfg(unix)]
+use std::time::Duration;
 use uuid::Uuid;
 use sha2::{Sha256, Digest};
 use default_args::default_args;
 use kill_tree::blocking::kill_tree;
 use sysinfo::System;
 
-type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+type Result<T> = std::result::Result<T, AugmentError>;
+
+/// Errors that can occur while discovering, rewriting, or locking IDE telemetry files.
+#[derive(Debug)]
+enum AugmentError {
+    /// Neither a JetBrains nor a VSCode installation could be found on this machine.
+    NoInstallationsFound,
+    /// An id file to lock was missing, or its permissions could not be set read-only.
+    FileLock { path: PathBuf, source: io::Error },
+    /// A `state.vscdb` query or connection failed.
+    Database { path: PathBuf, source: rusqlite::Error },
+    /// An embedded base64 constant failed to decode.
+    Decode(base64::DecodeError),
+    /// A decoded base64 constant was not valid UTF-8.
+    Utf8(std::string::FromUtf8Error),
+    /// `storage.json` failed to serialize (an unparsable file is read as empty).
+    Serde(serde_json::Error),
+    /// Writing a regenerated id file or `storage.json` failed.
+    Io { path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for AugmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AugmentError::NoInstallationsFound => {
+                write!(f, "No JetBrains or VSCode installations found")
+            }
+            AugmentError::FileLock { path, source } => {
+                write!(f, "Failed to lock file '{}': {}", path.display(), source)
+            }
+            AugmentError::Database { path, source } => {
+                write!(f, "Database error on '{}': {}", path.display(), source)
+            }
+            AugmentError::Decode(source) => write!(f, "Failed to decode embedded data: {}", source),
+            AugmentError::Utf8(source) => {
+                write!(f, "Decoded embedded data was not valid UTF-8: {}", source)
+            }
+            AugmentError::Serde(source) => write!(f, "Failed to (de)serialize storage.json: {}", source),
+            AugmentError::Io { path, source } => {
+                write!(f, "I/O error on '{}': {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AugmentError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AugmentError::NoInstallationsFound => None,
+            AugmentError::FileLock { source, .. } => Some(source),
+            AugmentError::Database { source, .. } => Some(source),
+            AugmentError::Decode(source) => Some(source),
+            AugmentError::Utf8(source) => Some(source),
+            AugmentError::Serde(source) => Some(source),
+            AugmentError::Io { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<base64::DecodeError> for AugmentError {
+    fn from(source: base64::DecodeError) -> Self {
+        AugmentError::Decode(source)
+    }
+}
+
+impl From<std::string::FromUtf8Error> for AugmentError {
+    fn from(source: std::string::FromUtf8Error) -> Self {
+        AugmentError::Utf8(source)
+    }
+}
+
+impl From<serde_json::Error> for AugmentError {
+    fn from(source: serde_json::Error) -> Self {
+        AugmentError::Serde(source)
+    }
+}
 
 #[derive(Parser)]
 #[command(name = "augment-vip")]
@@ -29,25 +113,110 @@ struct Args {
     /// Skip IDE termination
     #[arg(long)]
     no_terminate: bool,
+
+    /// Skip relaunching IDEs that were terminated
+    #[arg(long)]
+    no_restart: bool,
+
+    /// Keep sudo credentials fresh in the background for long runs (Unix only)
+    #[arg(long)]
+    sudoloop: bool,
+
+    /// Increase log verbosity (-v for debug, -vv for trace)
+    #[arg(short = 'v', long = "verbose", action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Suppress all log output except errors
+    #[arg(short = 'q', long = "quiet")]
+    quiet: bool,
+}
+
+fn init_logging(args: &Args) {
+    let level = if args.quiet {
+        log::LevelFilter::Error
+    } else {
+        match args.verbose {
+            0 => log::LevelFilter::Info,
+            1 => log::LevelFilter::Debug,
+            _ => log::LevelFilter::Trace,
+        }
+    };
+
+    env_logger::Builder::new()
+        .filter_level(level)
+        .format_timestamp(None)
+        .format_target(false)
+        .init();
 }
 
 fn main() {
     let args = Args::parse();
-    
+    init_logging(&args);
+
     #[cfg(target_os = "macos")]
     if let Err(e) = sudo2::escalate_if_needed() { // Request sudo permissions early on macOS
-        eprintln!("Warning: {}\n\nFile locking may not work properly!", e);
+        log::warn!("{}\n\nFile locking may not work properly!", e);
     }
 
-    if let Err(e) = run(&args) {
-        eprintln!("Error: {}", e);
+    #[cfg(unix)]
+    let sudoloop_stop = args.sudoloop.then(spawn_sudoloop);
+
+    let result = run(&args);
+
+    #[cfg(unix)]
+    stop_sudoloop(sudoloop_stop);
+
+    if let Err(e) = result {
+        log::error!("{}", e);
         pause(&args);
-        std::process::exit(1);
+        std::process::exit(exit_code_for(&e));
     }
 
     pause(&args);
 }
 
+/// Maps an [`AugmentError`] to a distinct process exit code so scripts driving
+/// this tool can distinguish "nothing to do" from a permission/IO failure.
+fn exit_code_for(error: &AugmentError) -> i32 {
+    match error {
+        AugmentError::NoInstallationsFound => 1,
+        AugmentError::FileLock { .. } => 3,
+        AugmentError::Database { .. } => 4,
+        AugmentError::Decode(_) | AugmentError::Utf8(_) | AugmentError::Serde(_) => 5,
+        AugmentError::Io { .. } => 6,
+    }
+}
+
+/// Spawns a background thread that runs `sudo -n -v` every ~50 seconds to
+/// refresh sudo's credential cache so it doesn't expire during a long run.
+/// Returns a flag the caller can set to stop the thread once `run` finishes.
+#[cfg(unix)]
+fn spawn_sudoloop() -> Arc<AtomicBool> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_thread = Arc::clone(&stop);
+
+    thread::spawn(move || {
+        while !stop_thread.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_secs(50));
+            if stop_thread.load(Ordering::Relaxed) {
+                break;
+            }
+            // Non-interactive refresh; if sudo needs a password again the failure
+            // is ignored and retried on the next tick, never blocking or prompting.
+            let _ = Command::new("sudo").args(["-n", "-v"]).status();
+        }
+    });
+
+    stop
+}
+
+#[cfg(unix)]
+fn stop_sudoloop(stop: Option<Arc<AtomicBool>>) {
+    if let Some(flag) = stop {
+        flag.store(true, Ordering::Relaxed);
+    }
+}
+
 fn pause(args: &Args) {
     if args.no_pause { return; }
     print!("\nPress Enter to exit...");
@@ -55,16 +224,286 @@ fn pause(args: &Args) {
     io::stdin().read_line(&mut String::new()).unwrap();
 }
 
-fn terminate_ides() {
+/// Everything needed to relaunch a terminated IDE process the way it was running.
+struct RestartTarget {
+    exe: PathBuf,
+    args: Vec<String>,
+    cwd: Option<PathBuf>,
+    env: Vec<(String, String)>,
+}
+
+/// How a [`RestartTarget`] should be relaunched, inferred from the environment
+/// markers its process was running with.
+enum SandboxKind {
+    Flatpak(String),
+    Snap(String),
+    AppImage(PathBuf),
+    Plain,
+}
+
+fn detect_sandbox(target: &RestartTarget) -> SandboxKind {
+    let env_var = |key: &str| target.env.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+
+    if let Some(id) = env_var("FLATPAK_ID") {
+        return SandboxKind::Flatpak(id);
+    }
+    if let Some(name) = env_var("SNAP_NAME").or_else(|| env_var("SNAP")) {
+        return SandboxKind::Snap(name);
+    }
+    if let Some(appimage) = env_var("APPIMAGE") {
+        return SandboxKind::AppImage(PathBuf::from(appimage));
+    }
+    SandboxKind::Plain
+}
+
+/// Strips sandbox-injected entries (Flatpak's `/app/...`, Snap's `/snap/...`)
+/// out of `PATH`/`XDG_DATA_DIRS` and de-duplicates the remaining entries,
+/// preserving order, so a relaunched child doesn't inherit the sandbox entries
+/// captured from the process it replaces.
+#[cfg(target_os = "linux")]
+fn normalize_linux_env(env: &[(String, String)]) -> Vec<(String, String)> {
+    let is_sandbox_entry = |entry: &str| {
+        entry.starts_with("/app/") || entry.contains("/snap/") || entry.starts_with("/var/lib/flatpak")
+    };
+
+    env.iter()
+        .map(|(key, value)| {
+            if key != "PATH" && key != "XDG_DATA_DIRS" {
+                return (key.clone(), value.clone());
+            }
+
+            let mut seen = std::collections::HashSet::new();
+            let cleaned: Vec<&str> = value
+                .split(':')
+                .filter(|entry| !entry.is_empty() && !is_sandbox_entry(entry))
+                .filter(|entry| seen.insert(*entry))
+                .collect();
+            (key.clone(), cleaned.join(":"))
+        })
+        .collect()
+}
+
+fn build_restart_command(target: &RestartTarget) -> Command {
+    match detect_sandbox(target) {
+        SandboxKind::Flatpak(id) => {
+            let mut command = Command::new("flatpak");
+            command.arg("run").arg(id);
+            command.args(&target.args);
+            command
+        }
+        SandboxKind::Snap(name) => {
+            let mut command = Command::new("snap");
+            command.arg("run").arg(name);
+            command.args(&target.args);
+            command
+        }
+        SandboxKind::AppImage(appimage_path) => {
+            let mut command = Command::new(appimage_path);
+            command.args(&target.args);
+            command
+        }
+        SandboxKind::Plain => {
+            let mut command = Command::new(&target.exe);
+            command.args(&target.args);
+            command
+        }
+    }
+}
+
+fn restart_ide(target: &RestartTarget) -> io::Result<()> {
+    let mut command = build_restart_command(target);
+
+    if let Some(cwd) = &target.cwd {
+        command.current_dir(cwd);
+    }
+
+    command.env_clear();
+    #[cfg(target_os = "linux")]
+    command.envs(normalize_linux_env(&target.env));
+    #[cfg(not(target_os = "linux"))]
+    command.envs(target.env.iter().cloned());
+
+    command.spawn()?;
+    Ok(())
+}
+
+fn restart_ides(targets: Vec<RestartTarget>) {
+    for target in targets {
+        log::info!("Restarting: {}", target.exe.display());
+        if let Err(e) = restart_ide(&target) {
+            log::warn!("Failed to restart {}: {}", target.exe.display(), e);
+        }
+    }
+}
+
+/// Kills every matching VSCode/augmentcode process tree, returning the
+/// deduplicated set of processes so they can be relaunched afterwards.
+fn terminate_ides() -> Vec<RestartTarget> {
+    let mut targets = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
     for (pid, process) in System::new_all().processes() {
-        let cmd_str = process.cmd().join(" ".as_ref()).to_string_lossy().to_string();
+        let cmd_str = process.cmd().join(" ");
         if !cmd_str.contains("vscode") && !cmd_str.contains(".augmentcode") { continue; }
+
+        // Skip Electron/Chromium helper processes (renderer, gpu-process, utility,
+        // crashpad handler, ...) - they're forked by the top-level editor process
+        // with specific IPC handles and can't be relaunched standalone.
+        let is_helper_process = process.cmd().iter().any(|arg| arg.starts_with("--type="));
+
+        if !is_helper_process {
+            if let Some(exe) = process.exe() {
+                let args: Vec<String> = process.cmd().iter().skip(1).map(|a| a.clone()).collect();
+
+                if seen.insert((exe.to_path_buf(), args.clone())) {
+                    targets.push(RestartTarget {
+                        exe: exe.to_path_buf(),
+                        args,
+                        cwd: process.cwd().map(|p| p.to_path_buf()),
+                        env: process
+                            .environ()
+                            .iter()
+                            .filter_map(|kv| kv.split_once('='))
+                            .map(|(k, v)| (k.to_string(), v.to_string()))
+                            .collect(),
+                    });
+                }
+            }
+        }
+
         if let Some(parent_pid) = process.parent() {
             let _ = kill_tree(parent_pid.as_u32());
         }
         let _ = kill_tree(pid.as_u32());
     }
-    // TODO: Restart IDEs
+
+    targets
+}
+
+/// Whether a regenerated storage value should be a plain UUID or a SHA-256
+/// hash of one.
+#[derive(Clone, Copy)]
+enum StorageValueKind {
+    Uuid,
+    Sha256,
+}
+
+/// A `storage.json` key, base64-encoded the way the rest of this tool embeds
+/// its telemetry key names, along with how its regenerated value is shaped.
+struct StorageKey {
+    encoded: &'static str,
+    kind: StorageValueKind,
+}
+
+impl StorageKey {
+    fn decode_name(&self) -> Result<String> {
+        Ok(String::from_utf8(general_purpose::STANDARD.decode(self.encoded)?)?)
+    }
+
+    fn regenerate(&self) -> String {
+        match self.kind {
+            StorageValueKind::Uuid => Uuid::new_v4().to_string(),
+            StorageValueKind::Sha256 => format!("{:x}", Sha256::digest(Uuid::new_v4().as_bytes())),
+        }
+    }
+}
+
+/// One editor family this tool knows how to clean telemetry from. New forks
+/// (Cursor, Windsurf, ...) are added by implementing this trait and adding a
+/// struct to the registry in `clean_up_telemetry`, instead of editing its control flow.
+trait IdeTarget {
+    fn name(&self) -> &'static str;
+
+    /// Every storage location this IDE might be installed in. Some locations
+    /// are directories containing `storage.json`/a `state.vscdb`, others are
+    /// bare id files.
+    fn discover_config_dirs(&self) -> Result<Vec<PathBuf>>;
+
+    /// Named id files to regenerate and lock inside a discovered config dir.
+    fn id_files(&self, config_dir: &Path) -> Result<Vec<PathBuf>>;
+
+    /// `storage.json` keys to regenerate inside a discovered config dir, if any.
+    fn storage_keys(&self) -> &[StorageKey];
+
+    /// `(count_query, delete_query)` to run against a discovered config dir's
+    /// `state.vscdb`, if this IDE family has one.
+    fn database_cleanup(&self) -> Result<Option<(String, String)>>;
+}
+
+struct JetBrains;
+
+impl IdeTarget for JetBrains {
+    fn name(&self) -> &'static str {
+        "JetBrains"
+    }
+
+    fn discover_config_dirs(&self) -> Result<Vec<PathBuf>> {
+        Ok(get_jetbrains_config_dir().into_iter().collect())
+    }
+
+    fn id_files(&self, config_dir: &Path) -> Result<Vec<PathBuf>> {
+        ["UGVybWFuZW50RGV2aWNlSWQ=", "UGVybWFuZW50VXNlcklk"]
+            .into_iter()
+            .map(|encoded| {
+                let name = general_purpose::STANDARD.decode(encoded)?;
+                let name = String::from_utf8(name)?;
+                Ok(config_dir.join(name))
+            })
+            .collect()
+    }
+
+    fn storage_keys(&self) -> &[StorageKey] {
+        &[]
+    }
+
+    fn database_cleanup(&self) -> Result<Option<(String, String)>> {
+        Ok(None)
+    }
+}
+
+struct VSCode;
+
+impl VSCode {
+    const STORAGE_KEYS: &'static [StorageKey] = &[
+        StorageKey { encoded: "dGVsZW1ldHJ5Lm1hY2hpbmVJZA==", kind: StorageValueKind::Sha256 },
+        StorageKey { encoded: "dGVsZW1ldHJ5LmRldkRldmljZUlk", kind: StorageValueKind::Uuid },
+        StorageKey { encoded: "dGVsZW1ldHJ5Lm1hY01hY2hpbmVJZA==", kind: StorageValueKind::Sha256 },
+        StorageKey { encoded: "c3RvcmFnZS5zZXJ2aWNlTWFjaGluZUlk", kind: StorageValueKind::Sha256 },
+    ];
+}
+
+impl IdeTarget for VSCode {
+    fn name(&self) -> &'static str {
+        "VSCode"
+    }
+
+    fn discover_config_dirs(&self) -> Result<Vec<PathBuf>> {
+        let id = general_purpose::STANDARD.decode("bWFjaGluZUlk")?;
+        let id = String::from_utf8(id)?;
+        Ok(get_vscode_files(&id).unwrap_or_default())
+    }
+
+    fn id_files(&self, _config_dir: &Path) -> Result<Vec<PathBuf>> {
+        // VSCode's only named id file is the bare config dir itself, handled
+        // directly in `clean_up_telemetry` since it isn't nested inside a storage directory.
+        Ok(Vec::new())
+    }
+
+    fn storage_keys(&self) -> &[StorageKey] {
+        Self::STORAGE_KEYS
+    }
+
+    fn database_cleanup(&self) -> Result<Option<(String, String)>> {
+        let decode = |encoded: &str| -> Result<String> {
+            let bytes = general_purpose::STANDARD.decode(encoded)?;
+            Ok(String::from_utf8(bytes)?)
+        };
+
+        Ok(Some((
+            decode("U0VMRUNUIENPVU5UKCopIEZST00gSXRlbVRhYmxlIFdIRVJFIGtleSBMSUtFICclYXVnbWVudCUnOw==")?,
+            decode("REVMRVRFIEZST00gSXRlbVRhYmxlIFdIRVJFIGtleSBMSUtFICclYXVnbWVudCUnOw==")?,
+        )))
+    }
 }
 
 fn get_jetbrains_config_dir() -> Option<PathBuf> {
@@ -76,6 +515,7 @@ fn get_jetbrains_config_dir() -> Option<PathBuf> {
 }
 
 fn get_vscode_files(id: &str) -> Option<Vec<PathBuf>> {
+    log::debug!("Searching for VSCode variants using id marker '{}'", id);
     let base_dirs = [dirs::config_dir(), dirs::home_dir(), dirs::data_dir()];
     let global_patterns = [
         &["User", "globalStorage"] as &[&str],
@@ -101,12 +541,15 @@ fn get_vscode_files(id: &str) -> Option<Vec<PathBuf>> {
 
                     // Global storage patterns
                     let global_paths: Vec<PathBuf> = global_patterns.iter().map(|pattern| {
-                        pattern.iter().fold(entry_path.clone(), |path, segment| path.join(segment))
+                        let candidate = pattern.iter().fold(entry_path.clone(), |path, segment| path.join(segment));
+                        log::debug!("Checking global storage candidate: {}", candidate.display());
+                        candidate
                     }).collect();
 
                     // Workspace storage patterns - enumerate all subdirectories
                     let workspace_paths: Vec<PathBuf> = workspace_patterns.iter().flat_map(|pattern| {
                         let workspace_base = pattern.iter().fold(entry_path.clone(), |path, segment| path.join(segment));
+                        log::debug!("Checking workspace storage base: {}", workspace_base.display());
                         if workspace_base.exists() {
                             fs::read_dir(&workspace_base)
                                 .into_iter()
@@ -129,19 +572,19 @@ fn get_vscode_files(id: &str) -> Option<Vec<PathBuf>> {
 }
 
 fn update_id_file(file_path: &Path) -> Result<()> {
-    println!("Updating file: {}", file_path.display());
+    log::info!("Updating file: {}", file_path.display());
 
     // Show old UUID if it exists
     if file_path.exists() {
         let old_uuid = fs::read_to_string(file_path).unwrap_or_default();
         if !old_uuid.is_empty() {
-            println!("Old UUID: {}", old_uuid);
+            log::info!("Old UUID: {}", old_uuid);
         }
     }
 
     // Show new UUID
     let new_uuid = Uuid::new_v4().to_string();
-    println!("New UUID: {}", new_uuid);
+    log::info!("New UUID: {}", new_uuid);
 
     // Delete the file if it exists
     if file_path.exists() {
@@ -156,138 +599,162 @@ fn update_id_file(file_path: &Path) -> Result<()> {
     }
 
     // Write new UUID to file
-    fs::write(file_path, new_uuid)?;
+    fs::write(file_path, new_uuid)
+        .map_err(|source| AugmentError::Io { path: file_path.to_path_buf(), source })?;
 
-    println!("Successfully wrote new UUID to file");
+    log::info!("Successfully wrote new UUID to file");
     Ok(())
 }
 
-fn update_vscode_files(vscode_file_path: &Path, vscode_keys: &[&str; 4]) -> Result<()> {
-    let storage_json_path = vscode_file_path.join("storage.json");
-    
-    if storage_json_path.exists() {
-        println!("Updating VSCode storage: {}", storage_json_path.display());
-
-        // Read existing storage.json or create empty object
-        let mut data: Map<String, Value> = storage_json_path.exists()
-            .then(|| fs::read_to_string(&storage_json_path).ok())
-            .flatten()
-            .and_then(|content| serde_json::from_str(&content).ok())
-            .unwrap_or_else(Map::new);
-
-        for key_encoded in vscode_keys {
-            let key = String::from_utf8(general_purpose::STANDARD.decode(key_encoded)?)?;
-
-            // Show old value if it exists
-            if let Some(old_value) = data.get(&key) {
-                println!("Old UUID: {}", old_value.as_str().unwrap_or_default());
-            }
+fn update_storage_json(config_dir: &Path, storage_keys: &[StorageKey]) -> Result<()> {
+    let storage_json_path = config_dir.join("storage.json");
 
-            // Generate and update new value
-            let new_value = if *key_encoded == "dGVsZW1ldHJ5LmRldkRldmljZUlk" {
-                Uuid::new_v4().to_string() // ... (something something look into something something) ...
-            } else {
-                format!("{:x}", Sha256::digest(Uuid::new_v4().as_bytes())) // Some fields are SHA-256 hashes
-            };
-            println!("New UUID: {}", new_value);
-            data.insert(key, Value::String(new_value));
-        }
+    if !storage_json_path.exists() {
+        return Ok(());
+    }
 
-        // Write back to file
-        let json_content = serde_json::to_string_pretty(&data)?;
-        fs::write(&storage_json_path, json_content)?;
+    log::info!("Updating VSCode storage: {}", storage_json_path.display());
 
-        println!("Successfully wrote new UUIDs to file");
-    }
-    
-    if vscode_file_path.exists() && vscode_file_path.is_file() { // it's the id file
-        update_id_file(vscode_file_path)?;
-        lock_file(vscode_file_path)?;
+    // Read existing storage.json or create empty object
+    let mut data: Map<String, Value> = fs::read_to_string(&storage_json_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(Map::new);
+
+    for storage_key in storage_keys {
+        let key = storage_key.decode_name()?;
+
+        // Show old value if it exists
+        if let Some(old_value) = data.get(&key) {
+            log::info!("Old UUID: {}", old_value.as_str().unwrap_or_default());
+        }
+
+        // Generate and update new value
+        let new_value = storage_key.regenerate();
+        log::info!("New UUID: {}", new_value);
+        data.insert(key, Value::String(new_value));
     }
-    
-    Ok(()) // continue
+
+    // Write back to file
+    let json_content = serde_json::to_string_pretty(&data)?;
+    fs::write(&storage_json_path, json_content)
+        .map_err(|source| AugmentError::Io { path: storage_json_path.clone(), source })?;
+
+    log::info!("Successfully wrote new UUIDs to file");
+    Ok(())
 }
 
 default_args! {
     fn clean_vscode_database(vscode_global_storage_path: &Path, count_query: &String, delete_query: &String, file_name: &String = &"state.vscdb".to_string()) -> Result<()> {
         let state_db_path = vscode_global_storage_path.join(file_name);
-    
+        log::debug!("Checking database file: {}", state_db_path.display());
+
         if !state_db_path.exists() {
             return Ok(());
         }
     
-        let conn = Connection::open(&state_db_path)?;
-    
+        let conn = Connection::open(&state_db_path)
+            .map_err(|source| AugmentError::Database { path: state_db_path.clone(), source })?;
+
         // Check how many rows would be deleted first
-        let rows_to_delete: i64 = conn.prepare(count_query)?.query_row([], |row| row.get(0))?;
+        let rows_to_delete: i64 = conn
+            .prepare(count_query)
+            .and_then(|mut stmt| stmt.query_row([], |row| row.get(0)))
+            .map_err(|source| AugmentError::Database { path: state_db_path.clone(), source })?;
         if rows_to_delete > 0 {
-            println!("Found {} potential entries to remove from '{}'", rows_to_delete, state_db_path.file_name().unwrap_or_default().to_string_lossy());
-    
+            log::info!("Found {} potential entries to remove from '{}'", rows_to_delete, state_db_path.file_name().unwrap_or_default().to_string_lossy());
+
             // Execute the delete query
-            conn.execute(delete_query, [])?;
-    
-            println!("Successfully removed {} entries from '{}'", rows_to_delete, state_db_path.file_name().unwrap_or_default().to_string_lossy());
+            conn.execute(delete_query, [])
+                .map_err(|source| AugmentError::Database { path: state_db_path.clone(), source })?;
+
+            log::info!("Successfully removed {} entries from '{}'", rows_to_delete, state_db_path.file_name().unwrap_or_default().to_string_lossy());
         }
     
         if file_name.ends_with(".backup") {
             return Ok(());
         }
-        clean_vscode_database_(vscode_global_storage_path, count_query, delete_query, &(file_name.to_string() + ".backup"))
+        let backup_file_name = file_name.to_string() + ".backup";
+        log::debug!("Chaining into backup database: {}", backup_file_name);
+        clean_vscode_database_(vscode_global_storage_path, count_query, delete_query, &backup_file_name)
     }
 }
 
 fn run(args: &Args) -> Result<()> {
-    if !args.no_terminate { terminate_ides(); }
-    let mut programs_found = false;
+    let restart_targets = if !args.no_terminate { terminate_ides() } else { Vec::new() };
 
-    // Try to find and update JetBrains
-    if let Some(jetbrains_dir) = get_jetbrains_config_dir() {
-        programs_found = true;
+    // Termination already happened above, so the user's editors must be relaunched
+    // regardless of whether the cleanup below succeeds - a locked file or DB error
+    // partway through shouldn't leave the IDE dead with no relaunch attempt.
+    let cleanup_result = clean_up_telemetry(args);
 
-        let id_files = ["UGVybWFuZW50RGV2aWNlSWQ=", "UGVybWFuZW50VXNlcklk"];
+    if !args.no_restart {
+        restart_ides(restart_targets);
+    }
 
-        for file_name in &id_files {
-            let file_path = jetbrains_dir.join(String::from_utf8(general_purpose::STANDARD.decode(file_name)?)?);
-            update_id_file(&file_path)?;
-            lock_file(&file_path)?;
-        }
+    cleanup_result
+}
 
-        println!("JetBrains ID files have been updated and locked successfully!");
-    } else {
-        println!("JetBrains configuration directory not found");
-    }
+fn clean_up_telemetry(args: &Args) -> Result<()> {
+    let mut programs_found = false;
+
+    let registry: Vec<Box<dyn IdeTarget>> = vec![Box::new(JetBrains), Box::new(VSCode)];
 
-    // Try to find and update VSCode variants
-    if let Some(vscode_dirs) = get_vscode_files(&String::from_utf8(general_purpose::STANDARD.decode("bWFjaGluZUlk")?)?) {
+    for target in &registry {
+        let config_dirs = target.discover_config_dirs()?;
+        if config_dirs.is_empty() {
+            log::warn!("{} installation not found", target.name());
+            continue;
+        }
         programs_found = true;
+        let mut databases_cleaned = false;
 
-        let vscode_keys = ["dGVsZW1ldHJ5Lm1hY2hpbmVJZA==", "dGVsZW1ldHJ5LmRldkRldmljZUlk", "dGVsZW1ldHJ5Lm1hY01hY2hpbmVJZA==", "c3RvcmFnZS5zZXJ2aWNlTWFjaGluZUlk"];
-        let count_query = String::from_utf8(general_purpose::STANDARD.decode("U0VMRUNUIENPVU5UKCopIEZST00gSXRlbVRhYmxlIFdIRVJFIGtleSBMSUtFICclYXVnbWVudCUnOw==")?)?;
-        let delete_query = String::from_utf8(general_purpose::STANDARD.decode("REVMRVRFIEZST00gSXRlbVRhYmxlIFdIRVJFIGtleSBMSUtFICclYXVnbWVudCUnOw==")?)?;
+        for config_dir in &config_dirs {
+            if config_dir.is_file() {
+                // Some targets (e.g. VSCode's machineId) discover a bare id file directly.
+                update_id_file(config_dir)?;
+                lock_file(config_dir)?;
+            } else {
+                if !target.storage_keys().is_empty() {
+                    update_storage_json(config_dir, target.storage_keys())?;
+                }
+                for id_file in target.id_files(config_dir)? {
+                    update_id_file(&id_file)?;
+                    lock_file(&id_file)?;
+                }
+            }
 
-        for vscode_dir in vscode_dirs {
-            update_vscode_files(&vscode_dir, &vscode_keys)?;
-            if !args.no_signout { clean_vscode_database!(&vscode_dir, &count_query, &delete_query)?; }
+            if !args.no_signout {
+                if let Some((count_query, delete_query)) = target.database_cleanup()? {
+                    clean_vscode_database!(config_dir, &count_query, &delete_query)?;
+                    databases_cleaned = true;
+                }
+            }
         }
 
-        println!("All found VSCode variants' ID files have been updated and databases cleaned successfully!");
-    } else {
-        println!("No VSCode variants found");
+        if databases_cleaned {
+            log::info!("{} ID files have been updated, locked, and databases cleaned successfully!", target.name());
+        } else {
+            log::info!("{} ID files have been updated and locked successfully!", target.name());
+        }
     }
 
     // Error only if no programs were found at all
     if !programs_found {
-        return Err("No JetBrains or VSCode installations found".into());
+        return Err(AugmentError::NoInstallationsFound);
     }
-    
+
     Ok(())
 }
 
 fn lock_file(file_path: &Path) -> Result<()> {
-    println!("Locking file: {}", file_path.display());
+    log::info!("Locking file: {}", file_path.display());
 
     if !file_path.exists() {
-        return Err(format!("File doesn't exist, can't lock: {}", file_path.display()).into());
+        return Err(AugmentError::FileLock {
+            path: file_path.to_path_buf(),
+            source: io::Error::new(io::ErrorKind::NotFound, "file does not exist"),
+        });
     }
 
     // Use platform-specific commands to lock the file
@@ -309,11 +776,14 @@ fn lock_file(file_path: &Path) -> Result<()> {
     // Always ensure file is read-only using Rust API regardless of platform command result
     #[cfg(not(target_os = "macos"))] // Rust's filesystem api doesn't work on mac
     {
-        let mut perms = fs::metadata(file_path)?.permissions();
+        let mut perms = fs::metadata(file_path)
+            .map_err(|source| AugmentError::FileLock { path: file_path.to_path_buf(), source })?
+            .permissions();
         perms.set_readonly(true);
-        fs::set_permissions(file_path, perms)?;
+        fs::set_permissions(file_path, perms)
+            .map_err(|source| AugmentError::FileLock { path: file_path.to_path_buf(), source })?;
     }
 
-    println!("Successfully locked file");
+    log::info!("Successfully locked file");
     Ok(())
 }